@@ -0,0 +1,215 @@
+// Copyright 2022 TiKV Project Authors. Licensed under Apache-2.0.
+
+//! Build script for `tirocks-sys`.
+//!
+//! The `crocksdb` C shim (`crocksdb/crocksdb_c.cc`) is always compiled from
+//! source and linked in, since its `crocksdb_*` symbols aren't part of
+//! upstream RocksDB. What's swappable is the underlying RocksDB library it's
+//! built against:
+//!
+//! By default this crate builds and links the vendored RocksDB submodule,
+//! which is reproducible but dominates compile time. When the
+//! `system-librocksdb` feature is enabled, it first looks for an existing
+//! installation before falling back to the vendored build:
+//!
+//! 1. `ROCKSDB_LIB_DIR` / `ROCKSDB_INCLUDE_DIR` environment variables.
+//! 2. `pkg-config` discovery of `librocksdb`.
+//! 3. The vendored submodule, built from source.
+//!
+//! Bindings are always regenerated against whichever headers were used to
+//! link, so the ABI the bindings describe always matches the linked library.
+//!
+//! Compression backends (`snappy`, `zlib`, `bz2`, `lz4`, `zstd`) are opt-in
+//! cargo features. Enabling one pulls in the corresponding `*-sys` crate,
+//! which links it and provides its headers, and passes the RocksDB macro
+//! that gates the codec in its own sources (e.g. `-DSNAPPY`, `-DBZIP2`)
+//! into the vendored build and into bindgen, so binaries never reference a
+//! codec that wasn't actually compiled in.
+
+use std::{
+    env,
+    path::{Path, PathBuf},
+};
+
+fn main() {
+    println!("cargo:rerun-if-env-changed=ROCKSDB_LIB_DIR");
+    println!("cargo:rerun-if-env-changed=ROCKSDB_INCLUDE_DIR");
+
+    let include_dir = discover_system_rocksdb().unwrap_or_else(build_bundled_rocksdb);
+
+    compile_shim(&include_dir);
+    generate_bindings(&include_dir, &compression_macros());
+}
+
+/// The preprocessor macros RocksDB's own sources use to gate each
+/// compression backend (not `WITH_*`, which is only the CMake option name),
+/// for the backends enabled via cargo features. Passed to both the vendored
+/// build and bindgen so the generated constants never reference a codec
+/// that wasn't actually compiled in.
+fn compression_macros() -> Vec<&'static str> {
+    let mut macros = Vec::new();
+    if cfg!(feature = "snappy") {
+        macros.push("SNAPPY");
+    }
+    if cfg!(feature = "zlib") {
+        macros.push("ZLIB");
+    }
+    if cfg!(feature = "bz2") {
+        macros.push("BZIP2");
+    }
+    if cfg!(feature = "lz4") {
+        macros.push("LZ4");
+    }
+    if cfg!(feature = "zstd") {
+        macros.push("ZSTD");
+    }
+    macros
+}
+
+/// Under the `system-librocksdb` feature, honor `ROCKSDB_LIB_DIR`/
+/// `ROCKSDB_INCLUDE_DIR` if both are set, then fall back to `pkg-config`
+/// discovery of `librocksdb`. Returns `None` (and the vendored submodule is
+/// built instead) when the feature is off or neither is found.
+#[cfg(feature = "system-librocksdb")]
+fn discover_system_rocksdb() -> Option<PathBuf> {
+    link_via_env().or_else(link_via_pkg_config)
+}
+
+#[cfg(not(feature = "system-librocksdb"))]
+fn discover_system_rocksdb() -> Option<PathBuf> {
+    None
+}
+
+#[cfg(feature = "system-librocksdb")]
+fn link_via_env() -> Option<PathBuf> {
+    let lib_dir = env::var("ROCKSDB_LIB_DIR").ok()?;
+    let include_dir = env::var("ROCKSDB_INCLUDE_DIR").ok()?;
+    println!("cargo:rustc-link-search=native={}", lib_dir);
+    println!("cargo:rustc-link-lib=dylib=rocksdb");
+    Some(PathBuf::from(include_dir))
+}
+
+#[cfg(feature = "system-librocksdb")]
+fn link_via_pkg_config() -> Option<PathBuf> {
+    let library = pkg_config::Config::new()
+        .cargo_metadata(true)
+        .probe("rocksdb")
+        .ok()?;
+    library.include_paths.into_iter().next()
+}
+
+/// Build the vendored RocksDB submodule itself and return its public
+/// include directory. Only used when no system RocksDB was discovered.
+fn build_bundled_rocksdb() -> PathBuf {
+    let mut config = cc::Build::new();
+    config.cpp(true);
+
+    // Lets the ownership path in `rocksdb_Status` (and the `r`/`s` slice
+    // conversions) be exercised under AddressSanitizer in tests.
+    if cfg!(feature = "asan") {
+        config.flag("-fsanitize=address");
+        println!("cargo:rustc-link-arg=-fsanitize=address");
+    }
+
+    for macro_name in compression_macros() {
+        config.define(macro_name, Some("1"));
+    }
+    // Codec headers exported by the enabled `*-sys` crates (`DEP_*_INCLUDE`).
+    for (key, value) in env::vars() {
+        if key.starts_with("DEP_") && key.ends_with("_INCLUDE") {
+            config.include(value);
+        }
+    }
+
+    bundled::compile_rocksdb(&mut config);
+
+    PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap()).join("rocksdb/include")
+}
+
+/// Compile the `crocksdb` C shim against `include_dir` and link it in. This
+/// runs regardless of whether RocksDB itself came from the vendored build
+/// or a system/pkg-config install, since `crocksdb_*` symbols are only
+/// defined by this shim.
+fn compile_shim(include_dir: &Path) {
+    let mut config = cc::Build::new();
+    config.cpp(true).include(include_dir).include("crocksdb");
+
+    if cfg!(feature = "asan") {
+        config.flag("-fsanitize=address");
+    }
+    for macro_name in compression_macros() {
+        config.define(macro_name, Some("1"));
+    }
+
+    config.file("crocksdb/crocksdb_c.cc").compile("crocksdb");
+}
+
+/// Generate bindings against `include_dir` and expose the result as
+/// `BINDING_PATH`, which `src/lib.rs` includes.
+fn generate_bindings(include_dir: &Path, compression_macros: &[&str]) {
+    let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
+    let bindings_path = out_dir.join("bindings.rs");
+
+    let mut builder = bindgen::Builder::default()
+        .header("crocksdb/crocksdb_c.h")
+        .clang_arg(format!("-I{}", include_dir.display()))
+        // `src/lib.rs` matches on the status enums' variants and prints them
+        // by name, so emit them as Rust enums rather than integer constants.
+        .rustified_enum("rocksdb_Status_.*")
+        // `rocksdb_Status` owns a heap pointer freed in `Drop`; a derived
+        // `Copy`/`Clone` would double-free it, so `src/lib.rs` provides a
+        // deep-copying `Clone` impl by hand instead.
+        .no_copy("rocksdb_Status");
+    for macro_name in compression_macros {
+        builder = builder.clang_arg(format!("-D{}", macro_name));
+    }
+
+    builder
+        .generate()
+        .expect("failed to generate rocksdb bindings")
+        .write_to_file(&bindings_path)
+        .expect("failed to write rocksdb bindings");
+
+    println!("cargo:rustc-env=BINDING_PATH={}", bindings_path.display());
+}
+
+/// Glue to the vendored RocksDB build, kept in its own module since it owns
+/// most of the submodule-specific path wrangling.
+mod bundled {
+    use std::{fs, path::Path};
+
+    use cc::Build;
+
+    pub fn compile_rocksdb(config: &mut Build) {
+        config.include("rocksdb/include").include("rocksdb");
+        for src in lib_sources() {
+            config.file(Path::new("rocksdb").join(src));
+        }
+        config.compile("rocksdb");
+    }
+
+    /// RocksDB's own `src.mk` is the source of truth for the full library
+    /// file list, so parse `LIB_SOURCES` out of it instead of hand-picking a
+    /// subset that would leave the shim's wider API surface unresolved.
+    fn lib_sources() -> Vec<String> {
+        let src_mk = fs::read_to_string("rocksdb/src.mk")
+            .expect("failed to read rocksdb/src.mk; is the rocksdb submodule checked out?");
+        parse_make_var(&src_mk, "LIB_SOURCES")
+    }
+
+    /// Parse a `NAME =\n  a.cc \\\n  b.cc \\\n...` make variable assignment
+    /// into its list of whitespace-separated values.
+    fn parse_make_var(contents: &str, name: &str) -> Vec<String> {
+        let marker = format!("{} =", name);
+        let start = contents
+            .find(&marker)
+            .unwrap_or_else(|| panic!("{} not found in src.mk", name));
+        contents[start + marker.len()..]
+            .lines()
+            .skip(1)
+            .take_while(|line| !line.trim().is_empty())
+            .flat_map(|line| line.trim().trim_end_matches('\\').split_whitespace())
+            .map(str::to_owned)
+            .collect()
+    }
+}
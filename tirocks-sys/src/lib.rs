@@ -6,6 +6,7 @@
 
 // bzip2 requires a user-defined `bz_internal_error` hook to handle errors
 // as it's an alloc free library. bzip2_sys provides a default implementation.
+#[cfg(feature = "bz2")]
 extern crate bzip2_sys;
 
 #[allow(clippy::all)]
@@ -144,32 +145,120 @@ impl Drop for rocksdb_Status {
     }
 }
 
+impl Clone for rocksdb_Status {
+    // `state_` is a heap pointer freed in `Drop`; a bitwise copy would leave
+    // two statuses owning (and eventually freeing) the same allocation. Deep
+    // copy by reallocating a fresh C++ array instead.
+    #[inline]
+    fn clone(&self) -> rocksdb_Status {
+        let state_ = match self.state() {
+            Some(state) => unsafe { crocksdb_to_cplus_array(r(state)) },
+            None => std::ptr::null(),
+        };
+        rocksdb_Status {
+            code_: self.code_,
+            subcode_: self.subcode_,
+            sev_: self.sev_,
+            state_,
+        }
+    }
+}
+
+impl std::fmt::Display for rocksdb_Status {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self.code_)?;
+        if self.subcode_ != rocksdb_Status_SubCode::kNone {
+            write!(f, "({:?})", self.subcode_)?;
+        }
+        if self.sev_ != rocksdb_Status_Severity::kNoError {
+            write!(f, " [{:?}]", self.sev_)?;
+        }
+        match self.state() {
+            Some(state) => write!(f, ": {}", String::from_utf8_lossy(state)),
+            None => Ok(()),
+        }
+    }
+}
+
+impl std::error::Error for rocksdb_Status {}
+
+/// A structured classification of a [`rocksdb_Status`], for callers that want
+/// to match on semantic error kinds instead of comparing raw bindgen
+/// constants.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatusKind {
+    NotFound,
+    Corruption,
+    NotSupported,
+    InvalidArgument,
+    IoError,
+    MergeInProgress,
+    Incomplete,
+    ShutdownInProgress,
+    TimedOut,
+    Aborted,
+    Busy,
+    Expired,
+    TryAgain,
+    CompactionTooLarge,
+    ColumnFamilyDropped,
+    Other,
+}
+
+impl rocksdb_Status {
+    /// Classify this status into a [`StatusKind`]. Returns `None` if the
+    /// status is ok.
+    #[inline]
+    pub fn kind(&self) -> Option<StatusKind> {
+        use rocksdb_Status_Code::*;
+
+        Some(match self.code_ {
+            kOk => return None,
+            kNotFound => StatusKind::NotFound,
+            kCorruption => StatusKind::Corruption,
+            kNotSupported => StatusKind::NotSupported,
+            kInvalidArgument => StatusKind::InvalidArgument,
+            kIOError => StatusKind::IoError,
+            kMergeInProgress => StatusKind::MergeInProgress,
+            kIncomplete => StatusKind::Incomplete,
+            kShutdownInProgress => StatusKind::ShutdownInProgress,
+            kTimedOut => StatusKind::TimedOut,
+            kAborted => StatusKind::Aborted,
+            kBusy => StatusKind::Busy,
+            kExpired => StatusKind::Expired,
+            kTryAgain => StatusKind::TryAgain,
+            kCompactionTooLarge => StatusKind::CompactionTooLarge,
+            kColumnFamilyDropped => StatusKind::ColumnFamilyDropped,
+            _ => StatusKind::Other,
+        })
+    }
+}
+
+/// Call an FFI function that reports errors through a trailing
+/// `rocksdb_Status*` out-parameter, returning early with `Err` if it's not
+/// ok.
+///
+/// `$func` accepts a path, not just a bare ident, so namespaced bindings can
+/// be called directly, e.g. `ffi_try!(ffi::crocksdb_open(opt, path))`, with
+/// no need to re-export every binding at the crate root. A trailing comma
+/// after the last argument is allowed.
 #[macro_export]
 macro_rules! ffi_try {
-    ($func:ident($($arg:expr),+)) => ({
+    ($($func:ident)::+ ($($arg:expr),* $(,)?)) => ({
         let mut status = $crate::rocksdb_Status::with_code($crate::rocksdb_Status_Code::kOk);
-        let res = $crate::$func($($arg),+, &mut status);
+        let res = $($func)::+($($arg,)* &mut status);
         if status.ok() {
             res
         } else {
             return Err(status.into());
         }
     });
-    ($func:ident()) => ({
-        let mut status = $crate::rocksdb_Status::with_code($crate::rocksdb_Status_Code::kOk);
-        let res = $crate::$func(&mut status);
-        if status.ok() {
-            res
-        } else {
-            return Err(status.into());
-        }
-    })
 }
 
 #[cfg(test)]
 mod tests {
     use crate::{
-        crocksdb_close, crocksdb_options_create, crocksdb_options_destroy,
+        crocksdb_close, crocksdb_open, crocksdb_options_create, crocksdb_options_destroy,
         crocksdb_options_set_create_if_missing, r, rocksdb_Status_Code,
     };
 
@@ -205,4 +294,47 @@ mod tests {
             crocksdb_options_destroy(opt);
         }
     }
+
+    #[test]
+    fn test_status_clone() {
+        let original = super::rocksdb_Status::with_error(rocksdb_Status_Code::kCorruption, "oops");
+        let cloned = original.clone();
+        assert_eq!(original.state(), cloned.state());
+        // Both own independent allocations; dropping one must not affect the
+        // other's `state()`.
+        drop(original);
+        assert_eq!(cloned.state(), Some(&b"oops"[..]));
+    }
+
+    #[test]
+    fn test_status_kind() {
+        use super::StatusKind;
+
+        assert_eq!(
+            super::rocksdb_Status::with_code(rocksdb_Status_Code::kOk).kind(),
+            None
+        );
+        assert_eq!(
+            super::rocksdb_Status::with_error(rocksdb_Status_Code::kCorruption, "oops").kind(),
+            Some(StatusKind::Corruption)
+        );
+        assert_eq!(
+            super::rocksdb_Status::with_error(rocksdb_Status_Code::kNotFound, "").kind(),
+            Some(StatusKind::NotFound)
+        );
+        assert_eq!(
+            super::rocksdb_Status::with_error(rocksdb_Status_Code::kMaxCode, "").kind(),
+            Some(StatusKind::Other)
+        );
+    }
+
+    #[test]
+    fn test_status_display() {
+        let ok = super::rocksdb_Status::with_code(rocksdb_Status_Code::kOk);
+        assert_eq!(format!("{}", ok), "kOk");
+
+        let with_message =
+            super::rocksdb_Status::with_error(rocksdb_Status_Code::kCorruption, "oops");
+        assert_eq!(format!("{}", with_message), "kCorruption: oops");
+    }
 }
\ No newline at end of file